@@ -11,10 +11,16 @@ use crate::{
     parse_accounts::{parse_accounts, ParsedAccount},
     parse_instruction::{parse, ParsedInstruction},
 };
+use serde::ser::SerializeTuple;
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 use solana_sdk::{
     clock::{Slot, UnixTimestamp},
     commitment_config::CommitmentConfig,
     deserialize_utils::default_on_eof,
+    hash::Hash,
     instruction::CompiledInstruction,
     message::{Message, MessageHeader},
     pubkey::Pubkey,
@@ -23,6 +29,399 @@ use solana_sdk::{
 };
 use std::fmt;
 
+/// A Solana address lookup table entry, as referenced by a v0 message.
+///
+/// Each lookup names an on-chain address lookup table and the indexes into
+/// that table's stored addresses that should be appended to the
+/// transaction's writable and readonly account lists, respectively.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageAddressTableLookup {
+    /// Address lookup table account key
+    pub account_key: Pubkey,
+    /// List of indexes used to load writable account addresses
+    pub writable_indexes: Vec<u8>,
+    /// List of indexes used to load readonly account addresses
+    pub readonly_indexes: Vec<u8>,
+}
+
+/// The addresses loaded on-chain from one or more address lookup tables,
+/// resolved and attached to a transaction's status meta so that consumers
+/// downstream of the runtime don't need their own lookup-table accounts.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedAddresses {
+    /// List of addresses for writable loaded accounts
+    pub writable: Vec<Pubkey>,
+    /// List of addresses for readonly loaded accounts
+    pub readonly: Vec<Pubkey>,
+}
+
+impl LoadedAddresses {
+    pub fn is_empty(&self) -> bool {
+        self.writable.is_empty() && self.readonly.is_empty()
+    }
+}
+
+pub mod v0 {
+    use super::*;
+
+    /// A Solana transaction message (v0).
+    ///
+    /// This format adds support for address table lookups, which allow a
+    /// transaction to reference accounts that were previously stored in an
+    /// on-chain address lookup table without including the full pubkey in
+    /// the message itself.
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Message {
+        /// The message header, identifying signed and read-only `account_keys`
+        pub header: MessageHeader,
+        /// List of accounts loaded by this transaction, excluding the loaded
+        /// address lookup table accounts
+        pub account_keys: Vec<Pubkey>,
+        /// The blockhash requested to be used for executing the transaction
+        pub recent_blockhash: Hash,
+        /// Instructions that invoke a designated program, where program
+        /// indexes map to the static `account_keys` or the `account_keys`
+        /// loaded from `address_table_lookups`
+        pub instructions: Vec<CompiledInstruction>,
+        /// List of address table lookups used to load additional accounts
+        /// for this transaction
+        pub address_table_lookups: Vec<MessageAddressTableLookup>,
+    }
+
+    impl Message {
+        /// Returns true if the account at the specified index was requested to
+        /// be writable. This method should not be used directly.
+        pub fn is_maybe_writable(&self, key_index: usize) -> bool {
+            if key_index < self.account_keys.len() {
+                is_static_writable_index(&self.header, self.account_keys.len(), key_index)
+            } else {
+                let loaded_addresses_index = key_index - self.account_keys.len();
+                let num_writable_lookup_accounts = self
+                    .address_table_lookups
+                    .iter()
+                    .map(|lookup| lookup.writable_indexes.len())
+                    .sum::<usize>();
+                loaded_addresses_index < num_writable_lookup_accounts
+            }
+        }
+    }
+}
+
+fn is_static_writable_index(header: &MessageHeader, num_account_keys: usize, index: usize) -> bool {
+    let num_signed_accounts = header.num_required_signatures as usize;
+    if index >= num_signed_accounts {
+        let num_unsigned_accounts = num_account_keys.saturating_sub(num_signed_accounts);
+        let num_writable_unsigned_accounts =
+            num_unsigned_accounts.saturating_sub(header.num_readonly_unsigned_accounts as usize);
+        index - num_signed_accounts < num_writable_unsigned_accounts
+    } else {
+        let num_writable_signed_accounts =
+            num_signed_accounts.saturating_sub(header.num_readonly_signed_accounts as usize);
+        index < num_writable_signed_accounts
+    }
+}
+
+/// The first byte of a bincode-serialized `VersionedMessage` indicates its
+/// version, reusing the high bit so that legacy `Message`s (whose first
+/// byte is `header.num_required_signatures`, always well under 0x80) remain
+/// indistinguishable from never-versioned messages on the wire.
+const MESSAGE_VERSION_PREFIX: u8 = 0x80;
+
+/// Either a legacy message or a v0 message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VersionedMessage {
+    Legacy(Message),
+    V0(v0::Message),
+}
+
+impl VersionedMessage {
+    pub fn header(&self) -> &MessageHeader {
+        match self {
+            Self::Legacy(message) => &message.header,
+            Self::V0(message) => &message.header,
+        }
+    }
+
+    pub fn static_account_keys(&self) -> &[Pubkey] {
+        match self {
+            Self::Legacy(message) => &message.account_keys,
+            Self::V0(message) => &message.account_keys,
+        }
+    }
+
+    pub fn recent_blockhash(&self) -> &Hash {
+        match self {
+            Self::Legacy(message) => &message.recent_blockhash,
+            Self::V0(message) => &message.recent_blockhash,
+        }
+    }
+
+    pub fn instructions(&self) -> &[CompiledInstruction] {
+        match self {
+            Self::Legacy(message) => &message.instructions,
+            Self::V0(message) => &message.instructions,
+        }
+    }
+
+    pub fn address_table_lookups(&self) -> Option<&[MessageAddressTableLookup]> {
+        match self {
+            Self::Legacy(_) => None,
+            Self::V0(message) => Some(&message.address_table_lookups),
+        }
+    }
+
+    pub fn is_signer(&self, index: usize) -> bool {
+        index < self.header().num_required_signatures as usize
+    }
+
+    pub fn is_maybe_writable(&self, index: usize) -> bool {
+        match self {
+            Self::Legacy(message) => {
+                index < message.account_keys.len()
+                    && is_static_writable_index(&message.header, message.account_keys.len(), index)
+            }
+            Self::V0(message) => message.is_maybe_writable(index),
+        }
+    }
+}
+
+impl Serialize for VersionedMessage {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Legacy(message) => message.serialize(serializer),
+            Self::V0(message) => {
+                let mut seq = serializer.serialize_tuple(2)?;
+                seq.serialize_element(&MESSAGE_VERSION_PREFIX)?;
+                seq.serialize_element(message)?;
+                seq.end()
+            }
+        }
+    }
+}
+
+enum MessagePrefix {
+    Legacy(u8),
+    Versioned(u8),
+}
+
+impl<'de> Deserialize<'de> for MessagePrefix {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PrefixVisitor;
+
+        impl<'de> Visitor<'de> for PrefixVisitor {
+            type Value = MessagePrefix;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("message prefix byte")
+            }
+
+            fn visit_u8<E>(self, byte: u8) -> std::result::Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                if byte & MESSAGE_VERSION_PREFIX != 0 {
+                    Ok(MessagePrefix::Versioned(byte & !MESSAGE_VERSION_PREFIX))
+                } else {
+                    Ok(MessagePrefix::Legacy(byte))
+                }
+            }
+        }
+
+        deserializer.deserialize_u8(PrefixVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionedMessage {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct MessageVisitor;
+
+        impl<'de> Visitor<'de> for MessageVisitor {
+            type Value = VersionedMessage;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("message")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let prefix: MessagePrefix = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                match prefix {
+                    MessagePrefix::Legacy(num_required_signatures) => {
+                        // Legacy `Message` encodes its vectors with short_vec lengths.
+                        #[derive(Deserialize)]
+                        #[serde(bound(deserialize = "T: Deserialize<'de>"))]
+                        struct ShortVec<T>(#[serde(with = "solana_sdk::short_vec")] Vec<T>);
+                        let num_readonly_signed_accounts = seq
+                            .next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                        let num_readonly_unsigned_accounts = seq
+                            .next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+                        let ShortVec(account_keys) = seq
+                            .next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(3, &self))?;
+                        let recent_blockhash = seq
+                            .next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(4, &self))?;
+                        let ShortVec(instructions) = seq
+                            .next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(5, &self))?;
+                        Ok(VersionedMessage::Legacy(Message {
+                            header: MessageHeader {
+                                num_required_signatures,
+                                num_readonly_signed_accounts,
+                                num_readonly_unsigned_accounts,
+                            },
+                            account_keys,
+                            recent_blockhash,
+                            instructions,
+                        }))
+                    }
+                    MessagePrefix::Versioned(0) => {
+                        let message = seq
+                            .next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                        Ok(VersionedMessage::V0(message))
+                    }
+                    MessagePrefix::Versioned(version) => Err(de::Error::custom(format!(
+                        "unsupported message version: {}",
+                        version
+                    ))),
+                }
+            }
+        }
+
+        deserializer.deserialize_tuple(usize::MAX, MessageVisitor)
+    }
+}
+
+/// A unified, ordered view over all the account keys referenced by a
+/// transaction: the message's static keys, followed by any addresses
+/// resolved from address lookup tables (writable, then readonly).
+#[derive(Clone, Copy, Debug)]
+pub struct AccountKeys<'a> {
+    static_keys: &'a [Pubkey],
+    loaded_addresses: Option<&'a LoadedAddresses>,
+}
+
+impl<'a> AccountKeys<'a> {
+    pub fn new(static_keys: &'a [Pubkey], loaded_addresses: Option<&'a LoadedAddresses>) -> Self {
+        Self {
+            static_keys,
+            loaded_addresses,
+        }
+    }
+
+    /// Returns the address of the account at the specified index, if any.
+    pub fn get(&self, index: usize) -> Option<&'a Pubkey> {
+        self.static_keys.get(index).or_else(|| {
+            self.loaded_addresses.and_then(|loaded_addresses| {
+                let loaded_index = index - self.static_keys.len();
+                if loaded_index < loaded_addresses.writable.len() {
+                    loaded_addresses.writable.get(loaded_index)
+                } else {
+                    loaded_addresses
+                        .readonly
+                        .get(loaded_index - loaded_addresses.writable.len())
+                }
+            })
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.static_keys.len()
+            + self
+                .loaded_addresses
+                .map(|loaded_addresses| loaded_addresses.writable.len() + loaded_addresses.readonly.len())
+                .unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a Pubkey> + '_ {
+        self.static_keys.iter().chain(
+            self.loaded_addresses
+                .into_iter()
+                .flat_map(|loaded_addresses| {
+                    loaded_addresses
+                        .writable
+                        .iter()
+                        .chain(loaded_addresses.readonly.iter())
+                }),
+        )
+    }
+}
+
+/// An atomically-committed sequence of instructions, either carrying a
+/// legacy message or a v0 message with address table lookups.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VersionedTransaction {
+    pub signatures: Vec<Signature>,
+    pub message: VersionedMessage,
+}
+
+impl VersionedTransaction {
+    pub fn message(&self) -> &VersionedMessage {
+        &self.message
+    }
+}
+
+impl From<Transaction> for VersionedTransaction {
+    fn from(transaction: Transaction) -> Self {
+        Self {
+            signatures: transaction.signatures,
+            message: VersionedMessage::Legacy(transaction.message),
+        }
+    }
+}
+
+/// The version of a transaction's message, as reported to RPC clients.
+/// Serializes as the string `"legacy"` or a bare version number so that
+/// the JS client can distinguish the two without a wrapper object.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged, rename_all = "camelCase")]
+pub enum TransactionVersion {
+    Legacy(Legacy),
+    Number(u8),
+}
+
+impl TransactionVersion {
+    pub const LEGACY: Self = Self::Legacy(Legacy::Legacy);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Legacy {
+    Legacy,
+}
+
+impl From<&VersionedMessage> for TransactionVersion {
+    fn from(message: &VersionedMessage) -> Self {
+        match message {
+            VersionedMessage::Legacy(_) => TransactionVersion::LEGACY,
+            VersionedMessage::V0(_) => TransactionVersion::Number(0),
+        }
+    }
+}
+
 /// A duplicate representation of an Instruction for pretty JSON serialization
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", untagged)]
@@ -39,13 +438,16 @@ pub enum UiParsedInstruction {
 }
 
 impl UiInstruction {
-    fn parse(instruction: &CompiledInstruction, message: &Message) -> Self {
-        let program_id = instruction.program_id(&message.account_keys);
-        if let Ok(parsed_instruction) = parse(program_id, instruction, &message.account_keys) {
+    fn parse(instruction: &CompiledInstruction, account_keys: &AccountKeys) -> Self {
+        let default_program_id = Pubkey::default();
+        let program_id = account_keys
+            .get(instruction.program_id_index as usize)
+            .unwrap_or(&default_program_id);
+        if let Ok(parsed_instruction) = parse(program_id, instruction, account_keys) {
             UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed_instruction))
         } else {
             UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(
-                UiPartiallyDecodedInstruction::from(instruction, &message.account_keys),
+                UiPartiallyDecodedInstruction::from(instruction, account_keys),
             ))
         }
     }
@@ -80,13 +482,21 @@ pub struct UiPartiallyDecodedInstruction {
 }
 
 impl UiPartiallyDecodedInstruction {
-    fn from(instruction: &CompiledInstruction, account_keys: &[Pubkey]) -> Self {
+    fn from(instruction: &CompiledInstruction, account_keys: &AccountKeys) -> Self {
         Self {
-            program_id: account_keys[instruction.program_id_index as usize].to_string(),
+            program_id: account_keys
+                .get(instruction.program_id_index as usize)
+                .map(|pubkey| pubkey.to_string())
+                .unwrap_or_default(),
             accounts: instruction
                 .accounts
                 .iter()
-                .map(|&i| account_keys[i as usize].to_string())
+                .map(|&index| {
+                    account_keys
+                        .get(index as usize)
+                        .map(|pubkey| pubkey.to_string())
+                        .unwrap_or_default()
+                })
                 .collect(),
             data: bs58::encode(instruction.data.clone()).into_string(),
         }
@@ -111,13 +521,13 @@ pub struct UiInnerInstructions {
 }
 
 impl UiInnerInstructions {
-    fn parse(inner_instructions: InnerInstructions, message: &Message) -> Self {
+    fn parse(inner_instructions: InnerInstructions, account_keys: &AccountKeys) -> Self {
         Self {
             index: inner_instructions.index,
             instructions: inner_instructions
                 .instructions
                 .iter()
-                .map(|ix| UiInstruction::parse(ix, message))
+                .map(|ix| UiInstruction::parse(ix, account_keys))
                 .collect(),
         }
     }
@@ -147,6 +557,8 @@ pub struct TransactionStatusMeta {
     pub inner_instructions: Option<Vec<InnerInstructions>>,
     #[serde(deserialize_with = "default_on_eof")]
     pub log_messages: Option<Vec<String>>,
+    #[serde(deserialize_with = "default_on_eof")]
+    pub loaded_addresses: LoadedAddresses,
 }
 
 impl Default for TransactionStatusMeta {
@@ -158,6 +570,32 @@ impl Default for TransactionStatusMeta {
             post_balances: vec![],
             inner_instructions: None,
             log_messages: None,
+            loaded_addresses: LoadedAddresses::default(),
+        }
+    }
+}
+
+/// A duplicate representation of LoadedAddresses for pretty JSON serialization
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiLoadedAddresses {
+    pub writable: Vec<String>,
+    pub readonly: Vec<String>,
+}
+
+impl From<LoadedAddresses> for UiLoadedAddresses {
+    fn from(loaded_addresses: LoadedAddresses) -> Self {
+        Self {
+            writable: loaded_addresses
+                .writable
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect(),
+            readonly: loaded_addresses
+                .readonly
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect(),
         }
     }
 }
@@ -173,10 +611,13 @@ pub struct UiTransactionStatusMeta {
     pub post_balances: Vec<u64>,
     pub inner_instructions: Option<Vec<UiInnerInstructions>>,
     pub log_messages: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loaded_addresses: Option<UiLoadedAddresses>,
 }
 
 impl UiTransactionStatusMeta {
-    fn parse(meta: TransactionStatusMeta, message: &Message) -> Self {
+    fn parse(meta: TransactionStatusMeta, account_keys: &AccountKeys) -> Self {
+        let loaded_addresses = meta.loaded_addresses;
         Self {
             err: meta.status.clone().err(),
             status: meta.status,
@@ -185,16 +626,18 @@ impl UiTransactionStatusMeta {
             post_balances: meta.post_balances,
             inner_instructions: meta.inner_instructions.map(|ixs| {
                 ixs.into_iter()
-                    .map(|ix| UiInnerInstructions::parse(ix, message))
+                    .map(|ix| UiInnerInstructions::parse(ix, account_keys))
                     .collect()
             }),
             log_messages: meta.log_messages,
+            loaded_addresses: (!loaded_addresses.is_empty()).then(|| loaded_addresses.into()),
         }
     }
 }
 
 impl From<TransactionStatusMeta> for UiTransactionStatusMeta {
     fn from(meta: TransactionStatusMeta) -> Self {
+        let loaded_addresses = meta.loaded_addresses;
         Self {
             err: meta.status.clone().err(),
             status: meta.status,
@@ -205,6 +648,7 @@ impl From<TransactionStatusMeta> for UiTransactionStatusMeta {
                 .inner_instructions
                 .map(|ixs| ixs.into_iter().map(|ix| ix.into()).collect()),
             log_messages: meta.log_messages,
+            loaded_addresses: (!loaded_addresses.is_empty()).then(|| loaded_addresses.into()),
         }
     }
 }
@@ -324,6 +768,25 @@ pub enum UiMessage {
     Raw(UiRawMessage),
 }
 
+/// A duplicate representation of a MessageAddressTableLookup for pretty JSON serialization
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiAddressTableLookup {
+    pub account_key: String,
+    pub writable_indexes: Vec<u8>,
+    pub readonly_indexes: Vec<u8>,
+}
+
+impl From<&MessageAddressTableLookup> for UiAddressTableLookup {
+    fn from(lookup: &MessageAddressTableLookup) -> Self {
+        Self {
+            account_key: lookup.account_key.to_string(),
+            writable_indexes: lookup.writable_indexes.clone(),
+            readonly_indexes: lookup.readonly_indexes.clone(),
+        }
+    }
+}
+
 /// A duplicate representation of a Message, in raw format, for pretty JSON serialization
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -332,6 +795,10 @@ pub struct UiRawMessage {
     pub account_keys: Vec<String>,
     pub recent_blockhash: String,
     pub instructions: Vec<UiCompiledInstruction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address_table_lookups: Option<Vec<UiAddressTableLookup>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<TransactionVersion>,
 }
 
 /// A duplicate representation of a Message, in parsed format, for pretty JSON serialization
@@ -341,21 +808,29 @@ pub struct UiParsedMessage {
     pub account_keys: Vec<ParsedAccount>,
     pub recent_blockhash: String,
     pub instructions: Vec<UiInstruction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address_table_lookups: Option<Vec<UiAddressTableLookup>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<TransactionVersion>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionWithStatusMeta {
-    pub transaction: Transaction,
+    pub transaction: VersionedTransaction,
     pub meta: Option<TransactionStatusMeta>,
 }
 
 impl TransactionWithStatusMeta {
     fn encode(self, encoding: UiTransactionEncoding) -> EncodedTransactionWithStatusMeta {
-        let message = self.transaction.message();
-        let meta = self.meta.map(|meta| meta.encode(encoding, message));
+        let loaded_addresses = self.meta.as_ref().map(|meta| meta.loaded_addresses.clone());
+        let account_keys = AccountKeys::new(
+            self.transaction.message().static_account_keys(),
+            loaded_addresses.as_ref(),
+        );
+        let meta = self.meta.map(|meta| meta.encode(encoding, &account_keys));
         EncodedTransactionWithStatusMeta {
-            transaction: EncodedTransaction::encode(self.transaction, encoding),
+            transaction: EncodedTransaction::encode(self.transaction, encoding, loaded_addresses),
             meta,
         }
     }
@@ -369,9 +844,9 @@ pub struct EncodedTransactionWithStatusMeta {
 }
 
 impl TransactionStatusMeta {
-    fn encode(self, encoding: UiTransactionEncoding, message: &Message) -> UiTransactionStatusMeta {
+    fn encode(self, encoding: UiTransactionEncoding, account_keys: &AccountKeys) -> UiTransactionStatusMeta {
         match encoding {
-            UiTransactionEncoding::JsonParsed => UiTransactionStatusMeta::parse(self, message),
+            UiTransactionEncoding::JsonParsed => UiTransactionStatusMeta::parse(self, account_keys),
             _ => self.into(),
         }
     }
@@ -404,7 +879,11 @@ pub enum EncodedTransaction {
 }
 
 impl EncodedTransaction {
-    pub fn encode(transaction: Transaction, encoding: UiTransactionEncoding) -> Self {
+    pub fn encode(
+        transaction: VersionedTransaction,
+        encoding: UiTransactionEncoding,
+        loaded_addresses: Option<LoadedAddresses>,
+    ) -> Self {
         match encoding {
             UiTransactionEncoding::Binary => EncodedTransaction::LegacyBinary(
                 bs58::encode(bincode::serialize(&transaction).unwrap()).into_string(),
@@ -418,35 +897,41 @@ impl EncodedTransaction {
                 encoding,
             ),
             UiTransactionEncoding::Json | UiTransactionEncoding::JsonParsed => {
-                let message = if encoding == UiTransactionEncoding::Json {
+                let message = transaction.message();
+                let account_keys =
+                    AccountKeys::new(message.static_account_keys(), loaded_addresses.as_ref());
+                let ui_message = if encoding == UiTransactionEncoding::Json {
                     UiMessage::Raw(UiRawMessage {
-                        header: transaction.message.header,
-                        account_keys: transaction
-                            .message
-                            .account_keys
+                        header: *message.header(),
+                        account_keys: message
+                            .static_account_keys()
                             .iter()
                             .map(|pubkey| pubkey.to_string())
                             .collect(),
-                        recent_blockhash: transaction.message.recent_blockhash.to_string(),
-                        instructions: transaction
-                            .message
-                            .instructions
+                        recent_blockhash: message.recent_blockhash().to_string(),
+                        instructions: message
+                            .instructions()
                             .iter()
                             .map(|instruction| instruction.into())
                             .collect(),
+                        address_table_lookups: message
+                            .address_table_lookups()
+                            .map(|lookups| lookups.iter().map(Into::into).collect()),
+                        version: Some(TransactionVersion::from(message)),
                     })
                 } else {
                     UiMessage::Parsed(UiParsedMessage {
-                        account_keys: parse_accounts(&transaction.message),
-                        recent_blockhash: transaction.message.recent_blockhash.to_string(),
-                        instructions: transaction
-                            .message
-                            .instructions
+                        account_keys: parse_accounts(&account_keys, message),
+                        recent_blockhash: message.recent_blockhash().to_string(),
+                        instructions: message
+                            .instructions()
                             .iter()
-                            .map(|instruction| {
-                                UiInstruction::parse(instruction, &transaction.message)
-                            })
+                            .map(|instruction| UiInstruction::parse(instruction, &account_keys))
                             .collect(),
+                        address_table_lookups: message
+                            .address_table_lookups()
+                            .map(|lookups| lookups.iter().map(Into::into).collect()),
+                        version: Some(TransactionVersion::from(message)),
                     })
                 };
                 EncodedTransaction::Json(UiTransaction {
@@ -455,12 +940,12 @@ impl EncodedTransaction {
                         .iter()
                         .map(|sig| sig.to_string())
                         .collect(),
-                    message,
+                    message: ui_message,
                 })
             }
         }
     }
-    pub fn decode(&self) -> Option<Transaction> {
+    pub fn decode(&self) -> Option<VersionedTransaction> {
         match self {
             EncodedTransaction::Json(_) => None,
             EncodedTransaction::LegacyBinary(blob) => bs58::decode(blob)
@@ -509,4 +994,73 @@ mod test {
         assert!(!status.satisfies_commitment(CommitmentConfig::default()));
         assert!(status.satisfies_commitment(CommitmentConfig::recent()));
     }
+
+    #[test]
+    fn test_versioned_transaction_round_trip_legacy() {
+        let legacy = Transaction::new_with_payer(&[], Some(&Pubkey::new_unique()));
+        let transaction = VersionedTransaction::from(legacy);
+        let bytes = bincode::serialize(&transaction).unwrap();
+        let deserialized: VersionedTransaction = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(transaction, deserialized);
+        assert!(matches!(deserialized.message, VersionedMessage::Legacy(_)));
+    }
+
+    #[test]
+    fn test_versioned_transaction_round_trip_v0() {
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(v0::Message {
+                header: MessageHeader {
+                    num_required_signatures: 1,
+                    num_readonly_signed_accounts: 0,
+                    num_readonly_unsigned_accounts: 1,
+                },
+                account_keys: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+                recent_blockhash: Hash::new_unique(),
+                instructions: vec![],
+                address_table_lookups: vec![MessageAddressTableLookup {
+                    account_key: Pubkey::new_unique(),
+                    writable_indexes: vec![0],
+                    readonly_indexes: vec![1],
+                }],
+            }),
+        };
+        let bytes = bincode::serialize(&transaction).unwrap();
+        let deserialized: VersionedTransaction = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(transaction, deserialized);
+        assert!(matches!(deserialized.message, VersionedMessage::V0(_)));
+    }
+
+    #[test]
+    fn test_legacy_transaction_bytes_deserialize_as_versioned_message() {
+        let legacy = Transaction::new_with_payer(&[], Some(&Pubkey::new_unique()));
+        let bytes = bincode::serialize(&legacy.message).unwrap();
+        let deserialized: VersionedMessage = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(deserialized, VersionedMessage::Legacy(legacy.message));
+    }
+
+    #[test]
+    fn test_account_keys_ordering() {
+        let static_keys = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let loaded_addresses = LoadedAddresses {
+            writable: vec![Pubkey::new_unique()],
+            readonly: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        };
+        let account_keys = AccountKeys::new(&static_keys, Some(&loaded_addresses));
+
+        assert_eq!(account_keys.len(), 5);
+        assert_eq!(account_keys.get(0), Some(&static_keys[0]));
+        assert_eq!(account_keys.get(1), Some(&static_keys[1]));
+        assert_eq!(account_keys.get(2), Some(&loaded_addresses.writable[0]));
+        assert_eq!(account_keys.get(3), Some(&loaded_addresses.readonly[0]));
+        assert_eq!(account_keys.get(4), Some(&loaded_addresses.readonly[1]));
+        assert_eq!(account_keys.get(5), None);
+
+        let expected: Vec<&Pubkey> = static_keys
+            .iter()
+            .chain(loaded_addresses.writable.iter())
+            .chain(loaded_addresses.readonly.iter())
+            .collect();
+        assert_eq!(account_keys.iter().collect::<Vec<_>>(), expected);
+    }
 }
@@ -0,0 +1,89 @@
+use crate::{AccountKeys, VersionedMessage};
+
+/// A duplicate representation of an account of a versioned message, for pretty JSON serialization
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedAccount {
+    pub pubkey: String,
+    pub writable: bool,
+    pub signer: bool,
+}
+
+pub fn parse_accounts(account_keys: &AccountKeys, message: &VersionedMessage) -> Vec<ParsedAccount> {
+    account_keys
+        .iter()
+        .enumerate()
+        .map(|(index, pubkey)| ParsedAccount {
+            pubkey: pubkey.to_string(),
+            writable: message.is_maybe_writable(index),
+            signer: message.is_signer(index),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::{LoadedAddresses, MessageAddressTableLookup, v0},
+        solana_sdk::{hash::Hash, message::MessageHeader, pubkey::Pubkey},
+    };
+
+    #[test]
+    fn test_parse_accounts_v0_with_lookups() {
+        let signed_writable_key = Pubkey::new_unique();
+        let unsigned_readonly_key = Pubkey::new_unique();
+        let loaded_writable_key = Pubkey::new_unique();
+        let loaded_readonly_key = Pubkey::new_unique();
+
+        let message = VersionedMessage::V0(v0::Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![signed_writable_key, unsigned_readonly_key],
+            recent_blockhash: Hash::new_unique(),
+            instructions: vec![],
+            address_table_lookups: vec![MessageAddressTableLookup {
+                account_key: Pubkey::new_unique(),
+                writable_indexes: vec![0],
+                readonly_indexes: vec![0],
+            }],
+        });
+        let loaded_addresses = LoadedAddresses {
+            writable: vec![loaded_writable_key],
+            readonly: vec![loaded_readonly_key],
+        };
+        let account_keys =
+            AccountKeys::new(message.static_account_keys(), Some(&loaded_addresses));
+
+        let parsed = parse_accounts(&account_keys, &message);
+
+        assert_eq!(
+            parsed,
+            vec![
+                ParsedAccount {
+                    pubkey: signed_writable_key.to_string(),
+                    writable: true,
+                    signer: true,
+                },
+                ParsedAccount {
+                    pubkey: unsigned_readonly_key.to_string(),
+                    writable: false,
+                    signer: false,
+                },
+                ParsedAccount {
+                    pubkey: loaded_writable_key.to_string(),
+                    writable: true,
+                    signer: false,
+                },
+                ParsedAccount {
+                    pubkey: loaded_readonly_key.to_string(),
+                    writable: false,
+                    signer: false,
+                },
+            ]
+        );
+    }
+}